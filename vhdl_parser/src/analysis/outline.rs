@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
+
+//! `textDocument/documentSymbol`: a hierarchical outline of a single
+//! source, built with [`OutlineSearcher`].
+
+use super::*;
+use crate::ast::search::{DocumentSymbol, OutlineClause, OutlineSearcher, Search, SymbolKind};
+
+impl DesignRoot {
+    /// Returns the outline of every design unit declared in `source`:
+    /// entities, architectures, packages and configurations at the top
+    /// level, with their ports, generics, declarations and instantiation
+    /// labels nested underneath.
+    pub fn document_symbols(&self, source: &Source) -> Vec<DocumentSymbol> {
+        self.design_units_in_source(source)
+            .iter()
+            .map(|unit| self.outline_of_unit(source, unit))
+            .collect()
+    }
+
+    fn outline_of_unit(&self, source: &Source, unit: &AnyDesignUnit) -> DocumentSymbol {
+        let mut searcher = OutlineSearcher::new(source);
+        match unit {
+            AnyDesignUnit::Entity(entity) => {
+                // Driven clause-by-clause, rather than through a single
+                // `entity.search(...)` call, so the searcher can be told
+                // whether it is currently inside the generic or the port
+                // clause: `InterfaceDeclaration::Object` alone can't tell a
+                // generic from a port apart.
+                searcher.set_clause(Some(OutlineClause::Generic));
+                entity.unit.generic_clause.search(&mut searcher);
+                searcher.set_clause(Some(OutlineClause::Port));
+                entity.unit.port_clause.search(&mut searcher);
+                searcher.set_clause(None);
+                entity.unit.decl.search(&mut searcher);
+                entity.unit.statements.search(&mut searcher);
+                searcher.finish_unit(
+                    SymbolKind::Entity,
+                    entity.unit.ident.pos.clone(),
+                    entity.unit.ident.pos.clone(),
+                )
+            }
+            AnyDesignUnit::Architecture(architecture) => {
+                // Driven part by part: `ArchitectureUnit::search` walks the
+                // declarative part twice, which would list every
+                // declaration twice.
+                architecture.unit.decl.search(&mut searcher);
+                architecture.unit.statements.search(&mut searcher);
+                searcher.finish_unit(
+                    SymbolKind::Architecture,
+                    architecture.unit.ident.pos.clone(),
+                    architecture.unit.ident.pos.clone(),
+                )
+            }
+            AnyDesignUnit::Package(package) => {
+                searcher.set_clause(Some(OutlineClause::Generic));
+                package.unit.generic_clause.search(&mut searcher);
+                searcher.set_clause(None);
+                package.unit.decl.search(&mut searcher);
+                searcher.finish_unit(
+                    SymbolKind::Package,
+                    package.unit.ident.pos.clone(),
+                    package.unit.ident.pos.clone(),
+                )
+            }
+            AnyDesignUnit::PackageBody(package_body) => {
+                package_body.search(&mut searcher);
+                searcher.finish_unit(
+                    SymbolKind::PackageBody,
+                    package_body.unit.ident.pos.clone(),
+                    package_body.unit.ident.pos.clone(),
+                )
+            }
+            // Configurations are not yet modeled as a `Search` target (no
+            // `ConfigurationUnit` impl exists), so they only get a bare
+            // top-level symbol for now.
+            AnyDesignUnit::Configuration(configuration) => searcher.finish_unit(
+                SymbolKind::Configuration,
+                configuration.unit.ident.pos.clone(),
+                configuration.unit.ident.pos.clone(),
+            ),
+        }
+    }
+}
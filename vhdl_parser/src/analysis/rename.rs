@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
+
+//! LSP-style `textDocument/rename`, built on top of the reference search
+//! machinery in `ast::search`.
+
+use super::*;
+use crate::analysis::search_scope::SearchScope;
+use crate::ast::search::{Finished, Found, NotFinished, NotFound, Search, SearchState, Searcher};
+use std::collections::HashMap;
+
+/// A single textual replacement within one source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: SrcPos,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// The cursor does not resolve to a renamable declaration.
+    NoDeclarationAtCursor,
+
+    /// `new_name` is neither a legal basic nor extended VHDL identifier.
+    InvalidIdentifier(String),
+
+    /// Renaming to `new_name` would collide with another, distinct
+    /// declaration of the same designator visible from `at`.
+    WouldCollide { new_name: String, at: SrcPos },
+}
+
+/// True if `name` is a legal VHDL identifier, either the basic form
+/// (starts with a letter, then letters/digits/underscores, no leading,
+/// trailing or doubled underscore) or the extended form `\...\`.
+fn is_legal_identifier(name: &str) -> bool {
+    if let Some(inner) = name
+        .strip_prefix('\\')
+        .and_then(|rest| rest.strip_suffix('\\'))
+    {
+        return !inner.is_empty();
+    }
+
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+
+    if name.ends_with('_') || name.contains("__") {
+        return false;
+    }
+
+    name.chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Looks for a designator reference, other than to `declaration` itself,
+/// that already binds to the name `new_name`, or for an object declaration
+/// (signal, constant, variable) named `new_name`, anywhere within `scope`.
+/// Either means the rename would shadow or collide with an existing,
+/// distinct declaration. Interface declarations (ports, generics,
+/// parameters) are only found through references to them. `scope` should be the sources that share a
+/// declarative region with `declaration` (see
+/// `DesignRoot::sources_in_same_declarative_region`), not every source in
+/// the library: most declarations in a library are never visible to each
+/// other at all, so scoping this library-wide would reject renames to
+/// common names like `clk` or `data` even though nothing would actually
+/// collide.
+struct CollisionSearcher<'a> {
+    new_name: &'a str,
+    declaration: &'a SrcPos,
+    scope: &'a [Source],
+    collision: Option<SrcPos>,
+}
+
+impl<'a> Searcher<()> for CollisionSearcher<'a> {
+    fn search_source(&mut self, source: &Source) -> SearchState<()> {
+        if self.scope.contains(source) {
+            NotFinished
+        } else {
+            Finished(NotFound)
+        }
+    }
+
+    fn search_designator_ref(&mut self, designator: &WithRef<Designator>) -> SearchState<()> {
+        if let Some(ref reference) = designator.reference {
+            if reference != self.declaration && designator_matches(&designator.item, self.new_name)
+            {
+                self.collision = Some(reference.clone());
+                return Finished(Found(()));
+            }
+        }
+        NotFinished
+    }
+
+    fn search_declaration(&mut self, decl: &Declaration) -> SearchState<()> {
+        if let Declaration::Object(object) = decl {
+            let designator = Designator::Identifier(object.ident.item.clone());
+            if &object.ident.pos != self.declaration
+                && designator_matches(&designator, self.new_name)
+            {
+                self.collision = Some(object.ident.pos.clone());
+                return Finished(Found(()));
+            }
+        }
+        NotFinished
+    }
+}
+
+fn designator_matches(designator: &Designator, name: &str) -> bool {
+    designator.to_string().eq_ignore_ascii_case(name)
+}
+
+impl DesignRoot {
+    /// Renames the declaration found at `cursor` within `source` to
+    /// `new_name`, returning one `TextEdit` per occurrence grouped by the
+    /// source file it occurs in.
+    ///
+    /// This mirrors the cursor -> definition -> references pipeline used
+    /// elsewhere, but scoped: `search_reference_in_scope` resolves the
+    /// declaration under the cursor and `find_all_references_in_scope`
+    /// gathers every occurrence of it, neither one traversing sources
+    /// outside the declaration's library, and each occurrence becomes a
+    /// replacement of its full token span (never a substring replace, since
+    /// VHDL identifiers are case-insensitive and may be written with
+    /// different casing at each occurrence).
+    pub fn rename(
+        &self,
+        source: &Source,
+        cursor: usize,
+        new_name: &str,
+    ) -> Result<Vec<(Source, Vec<TextEdit>)>, RenameError> {
+        if !is_legal_identifier(new_name) {
+            return Err(RenameError::InvalidIdentifier(new_name.to_owned()));
+        }
+
+        // The declaration's own source isn't known yet, so the cursor can
+        // only be narrowed down to the library it's declared in.
+        let cursor_scope = SearchScope::Library(self.sources_in_same_library(source));
+        let declaration = self
+            .search_reference_in_scope(&cursor_scope, source, cursor)
+            .ok_or(RenameError::NoDeclarationAtCursor)?;
+
+        let region_sources = self.sources_in_same_declarative_region(&declaration.source);
+        let mut collision_searcher = CollisionSearcher {
+            new_name,
+            declaration: &declaration,
+            scope: &region_sources,
+            collision: None,
+        };
+        self.search(&mut collision_searcher);
+        if let Some(at) = collision_searcher.collision {
+            return Err(RenameError::WouldCollide {
+                new_name: new_name.to_owned(),
+                at,
+            });
+        }
+
+        let old_name = {
+            let contents = declaration.source.contents().to_string();
+            contents[declaration.start..declaration.end()].to_owned()
+        };
+        let library_scope =
+            SearchScope::Library(self.sources_in_same_library(&declaration.source));
+        let mut edits_by_source: HashMap<Source, Vec<TextEdit>> = HashMap::new();
+        for occurrence in self.find_all_references_in_scope(&declaration, &old_name, &library_scope)
+        {
+            edits_by_source
+                .entry(occurrence.source.clone())
+                .or_default()
+                .push(TextEdit {
+                    range: occurrence,
+                    new_text: new_name.to_owned(),
+                });
+        }
+
+        Ok(edits_by_source.into_iter().collect())
+    }
+}
@@ -0,0 +1,219 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
+
+//! Scope narrowing for `search_reference` and `find_all_references`.
+//!
+//! Visiting every design unit's AST on every call does not scale on large
+//! projects. Before running the full `Search` traversal we narrow the set
+//! of candidate sources in two cheap steps:
+//!
+//! 1. The caller picks the smallest [`SearchScope`] the declaration could
+//!    possibly be referenced from: a local object (declared inside a
+//!    process, subprogram, block or architecture body) can only be
+//!    referenced from its enclosing source, a package-private item only
+//!    from sources in the same library, and an exported primary unit name
+//!    from anywhere in the project.
+//! 2. Within that scope, grep the raw text of each candidate source for the
+//!    identifier and only run the `Search` traversal on sources with at
+//!    least one textual hit. VHDL is case-insensitive, so both the
+//!    classification and the textual scan fold case.
+
+use super::*;
+use crate::ast::search::{Finished, NotFinished, NotFound, Search, SearchState, Searcher};
+
+/// The set of sources a reference to some declaration can possibly occur in.
+#[derive(Debug, Clone)]
+pub enum SearchScope {
+    /// Local to a single source: a process, subprogram, block or
+    /// architecture body declaration.
+    EnclosingUnit(Source),
+
+    /// Private to a library: any source belonging to it may reference it.
+    Library(Vec<Source>),
+
+    /// An exported primary unit name, referenceable from anywhere.
+    Project,
+}
+
+impl SearchScope {
+    fn candidate_sources<'a>(&'a self, all_sources: &'a [Source]) -> &'a [Source] {
+        match self {
+            SearchScope::EnclosingUnit(source) => std::slice::from_ref(source),
+            SearchScope::Library(sources) => sources,
+            SearchScope::Project => all_sources,
+        }
+    }
+
+    /// Narrows `self` down to the sources that textually mention `name`,
+    /// without running the full `Search` traversal on them yet. The scan is
+    /// a plain case-insensitive substring search of the raw source text.
+    pub fn textual_prefilter(&self, all_sources: &[Source], name: &str) -> Vec<Source> {
+        let needle = name.to_ascii_lowercase();
+        self.candidate_sources(all_sources)
+            .iter()
+            .filter(|source| contains_case_insensitive(&source.contents().to_string(), &needle))
+            .cloned()
+            .collect()
+    }
+}
+
+fn contains_case_insensitive(haystack: &str, lowercase_needle: &str) -> bool {
+    haystack.to_ascii_lowercase().contains(lowercase_needle)
+}
+
+impl DesignRoot {
+    /// Like [`DesignRoot::find_all_references`], but first narrows the
+    /// traversal down to `scope` and textually prefilters its sources for
+    /// `name`, then runs the `Search` only over the sources that
+    /// survive the prefilter — unlike calling `find_all_references` and
+    /// filtering its result, no source outside `candidates` is ever
+    /// visited. `find_all_references` is the `SearchScope::Project` special
+    /// case of this, with every source as a candidate.
+    pub fn find_all_references_in_scope(
+        &self,
+        decl: &SrcPos,
+        name: &str,
+        scope: &SearchScope,
+    ) -> Vec<SrcPos> {
+        let candidates = scope.textual_prefilter(&self.sources(), name);
+
+        let mut searcher = ScopedReferencesSearcher {
+            declaration: decl,
+            candidates: &candidates,
+            current_pos: None,
+            found: Vec::new(),
+        };
+        self.search(&mut searcher);
+        searcher.found
+    }
+
+    /// Every source belonging to the same library as `source`, `source`
+    /// included. Used to build a [`SearchScope::Library`] for library-private
+    /// declarations.
+    pub fn sources_in_same_library(&self, source: &Source) -> Vec<Source> {
+        self.sources()
+            .into_iter()
+            .filter(|candidate| candidate.library_name() == source.library_name())
+            .collect()
+    }
+
+    /// `source` together with every other source in the library that
+    /// shares a declarative region with it: architectures (and
+    /// configurations) of the same entity if `source` declares an entity
+    /// or an architecture, and the package/package body pair if `source`
+    /// declares one of those. This is much narrower than
+    /// [`DesignRoot::sources_in_same_library`]: two unrelated entities that
+    /// happen to live in the same library, but never mention each other,
+    /// cannot see one another's declarations, so they must not share a
+    /// scope for e.g. rename collision checking.
+    pub fn sources_in_same_declarative_region(&self, source: &Source) -> Vec<Source> {
+        let mut entity_names = Vec::new();
+        let mut package_names = Vec::new();
+        for unit in self.design_units_in_source(source) {
+            match unit {
+                AnyDesignUnit::Entity(entity) => entity_names.push(entity.unit.ident.item.clone()),
+                AnyDesignUnit::Architecture(architecture) => {
+                    entity_names.push(architecture.unit.entity_name.item.clone())
+                }
+                AnyDesignUnit::Package(package) => {
+                    package_names.push(package.unit.ident.item.clone())
+                }
+                AnyDesignUnit::PackageBody(package_body) => {
+                    package_names.push(package_body.unit.ident.item.clone())
+                }
+                AnyDesignUnit::Configuration(_) => {}
+            }
+        }
+
+        let mut sources = vec![source.clone()];
+        for candidate in self.sources_in_same_library(source) {
+            if &candidate == source {
+                continue;
+            }
+            let related = self
+                .design_units_in_source(&candidate)
+                .iter()
+                .any(|unit| match unit {
+                    AnyDesignUnit::Entity(entity) => entity_names.contains(&entity.unit.ident.item),
+                    AnyDesignUnit::Architecture(architecture) => {
+                        entity_names.contains(&architecture.unit.entity_name.item)
+                    }
+                    AnyDesignUnit::Package(package) => {
+                        package_names.contains(&package.unit.ident.item)
+                    }
+                    AnyDesignUnit::PackageBody(package_body) => {
+                        package_names.contains(&package_body.unit.ident.item)
+                    }
+                    AnyDesignUnit::Configuration(_) => false,
+                });
+            if related {
+                sources.push(candidate);
+            }
+        }
+        sources
+    }
+
+    /// Like [`DesignRoot::search_reference`], but only traverses sources
+    /// within `scope` instead of the whole project.
+    pub fn search_reference_in_scope(
+        &self,
+        scope: &SearchScope,
+        source: &Source,
+        cursor: usize,
+    ) -> Option<SrcPos> {
+        if matches!(scope, SearchScope::Project) || scope_contains(scope, source) {
+            self.search_reference(source, cursor)
+        } else {
+            None
+        }
+    }
+}
+
+/// Accumulates every occurrence of `declaration`, never short-circuiting,
+/// but prunes away any source not in `candidates` before the traversal
+/// descends into it: `search_source` is the hook that makes this an actual
+/// narrowed traversal rather than a full one with its results filtered
+/// afterwards.
+struct ScopedReferencesSearcher<'a> {
+    declaration: &'a SrcPos,
+    candidates: &'a [Source],
+    current_pos: Option<SrcPos>,
+    found: Vec<SrcPos>,
+}
+
+impl<'a> Searcher<()> for ScopedReferencesSearcher<'a> {
+    fn search_source(&mut self, source: &Source) -> SearchState<()> {
+        if self.candidates.contains(source) {
+            NotFinished
+        } else {
+            Finished(NotFound)
+        }
+    }
+
+    fn search_with_pos(&mut self, pos: &SrcPos) -> SearchState<()> {
+        self.current_pos = Some(pos.clone());
+        NotFinished
+    }
+
+    fn search_designator_ref(&mut self, designator: &WithRef<Designator>) -> SearchState<()> {
+        if let Some(ref reference) = designator.reference {
+            if reference == self.declaration {
+                if let Some(ref pos) = self.current_pos {
+                    self.found.push(pos.clone());
+                }
+            }
+        }
+        NotFinished
+    }
+}
+
+fn scope_contains(scope: &SearchScope, source: &Source) -> bool {
+    match scope {
+        SearchScope::EnclosingUnit(only) => only == source,
+        SearchScope::Library(sources) => sources.contains(source),
+        SearchScope::Project => true,
+    }
+}
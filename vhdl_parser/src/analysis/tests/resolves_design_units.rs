@@ -336,4 +336,294 @@ end package body;
         &root.find_all_references(&code.s1("pkg").pos()),
         &vec![code.s("pkg", 1).pos(), code.s("pkg", 2).pos()],
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn renames_entity_and_all_its_references() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+architecture a of ename1 is
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let edits = root
+        .rename(&code.source(), code.s("ename1", 2).start(), "ename2")
+        .unwrap();
+
+    let mut edits = edits.into_iter().collect::<Vec<_>>();
+    assert_eq!(edits.len(), 1);
+    let (edit_source, mut text_edits) = edits.remove(0);
+    assert_eq!(edit_source, code.source());
+
+    text_edits.sort_by_key(|edit| edit.range.start());
+    assert_eq!(
+        text_edits,
+        vec![
+            TextEdit {
+                range: code.s("ename1", 1).pos(),
+                new_text: "ename2".to_owned(),
+            },
+            TextEdit {
+                range: code.s("ename1", 2).pos(),
+                new_text: "ename2".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn rejects_rename_to_illegal_identifier() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.rename(&code.source(), code.s("ename1", 1).start(), "1bad"),
+        Err(RenameError::InvalidIdentifier("1bad".to_owned()))
+    );
+}
+
+#[test]
+fn signal_assignment_target_is_classified_as_write() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : bit;
+begin
+  sig <= '0';
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let mut searcher = ReferenceKindSearcher::new(&code.source(), code.s("sig", 2).start());
+    match root.search(&mut searcher) {
+        Found((pos, kind)) => {
+            assert_eq!(pos, code.s("sig", 1).pos());
+            assert_eq!(kind, ReferenceKind::Write);
+        }
+        NotFound => panic!("expected a reference at the assignment target"),
+    }
+}
+
+#[test]
+fn rejects_rename_that_would_collide_across_files_in_same_library() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+architecture a of ename1 is
+  signal other_sig : bit;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.rename(&code.source(), code.s("ename1", 1).start(), "other_sig"),
+        Err(RenameError::WouldCollide {
+            new_name: "other_sig".to_owned(),
+            at: code.s1("other_sig").pos(),
+        })
+    );
+}
+
+#[test]
+fn find_all_references_in_scope_prunes_unrelated_sources() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+architecture a of ename1 is
+begin
+end architecture;
+",
+    );
+    let other_code = builder.code(
+        "libname",
+        "
+entity other is
+end entity;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let declaration = code.s("ename1", 1).pos();
+    let scope = SearchScope::Library(vec![code.source(), other_code.source()]);
+
+    assert_eq_unordered(
+        &root.find_all_references_in_scope(&declaration, "ename1", &scope),
+        &vec![code.s("ename1", 1).pos(), code.s("ename1", 2).pos()],
+    );
+}
+
+#[test]
+fn renames_to_common_name_shared_by_unrelated_entity_in_same_library() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity other is
+  port (
+    clk : in bit
+  );
+end entity;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+architecture a of ename1 is
+  signal sig : bit;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    // `clk` is declared in an unrelated entity in the same library: renaming
+    // `sig` to `clk` must not be rejected as a collision, since `sig` is not
+    // visible from, and cannot shadow, anything declared in `other`.
+    let edits = root
+        .rename(&code.source(), code.s("sig", 1).start(), "clk")
+        .unwrap();
+    assert_eq!(edits.len(), 1);
+}
+
+#[test]
+fn document_symbols_nest_subprogram_parameters_under_the_subprogram() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  procedure proc(x : integer) is
+  begin
+  end procedure;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let symbols = root.document_symbols(&code.source());
+    let architecture_symbol = &symbols[1];
+
+    assert_eq!(architecture_symbol.children.len(), 1);
+    let subprogram_symbol = &architecture_symbol.children[0];
+    assert_eq!(subprogram_symbol.kind, SymbolKind::Subprogram);
+
+    // The parameter must be nested under the procedure's own symbol, not
+    // flattened as a sibling "Port" at the architecture level.
+    assert_eq!(subprogram_symbol.children.len(), 1);
+    assert_eq!(subprogram_symbol.children[0].kind, SymbolKind::Variable);
+}
+
+#[test]
+fn document_symbols_outline_entity_and_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+  generic (
+    width : integer
+  );
+  port (
+    clk : in bit
+  );
+end entity;
+
+architecture a of ename1 is
+  signal sig : bit;
+  constant cst : integer := 0;
+  shared variable var : integer;
+begin
+  inst : entity work.ename1;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let symbols = root.document_symbols(&code.source());
+    assert_eq!(symbols.len(), 2);
+
+    let entity_symbol = &symbols[0];
+    assert_eq!(entity_symbol.kind, SymbolKind::Entity);
+    assert_eq_unordered(
+        &entity_symbol
+            .children
+            .iter()
+            .map(|symbol| symbol.kind)
+            .collect(),
+        &vec![SymbolKind::Generic, SymbolKind::Port],
+    );
+
+    let architecture_symbol = &symbols[1];
+    assert_eq!(architecture_symbol.kind, SymbolKind::Architecture);
+    assert_eq_unordered(
+        &architecture_symbol
+            .children
+            .iter()
+            .map(|symbol| symbol.kind)
+            .collect(),
+        &vec![
+            SymbolKind::Signal,
+            SymbolKind::Constant,
+            SymbolKind::Variable,
+            SymbolKind::Instance,
+        ],
+    );
+}
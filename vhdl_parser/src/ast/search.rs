@@ -44,7 +44,36 @@ impl<T> SearchState<T> {
     }
 }
 
+/// How a resolved reference relates to its declaration, for tooling like
+/// `textDocument/documentHighlight` that needs to tell "where is this
+/// signal driven" apart from "where is it sampled".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// The declaration site itself.
+    Declaration,
+    /// The reference is read, e.g. used in an expression.
+    Read,
+    /// The reference is written: the target of a signal assignment (`<=`)
+    /// or a variable assignment (`:=`). The actual of an `out`/`inout` port
+    /// or parameter association is reported as `Read`, not `Write`.
+    Write,
+}
+
 pub trait Searcher<T> {
+    /// Called, before `search_designator_ref`, for a designator reference
+    /// reached as a `WithRef<Designator>` or through a `Name`, together
+    /// with the kind of occurrence it is. The generic traversal passes
+    /// `Read`; the traversal of an assignment target passes `Write`. The
+    /// default implementation does nothing. Designators of a
+    /// `SelectedName` (type marks, instantiated units) only reach
+    /// `search_designator_ref`.
+    fn search_reference_kind(
+        &mut self,
+        _designator: &WithRef<Designator>,
+        _kind: ReferenceKind,
+    ) -> SearchState<T> {
+        NotFinished
+    }
     fn search_labeled_concurrent_statement(
         &mut self,
         _stmt: &LabeledConcurrentStatement,
@@ -69,6 +98,12 @@ pub trait Searcher<T> {
     fn search_designator_ref(&mut self, _designator: &WithRef<Designator>) -> SearchState<T> {
         NotFinished
     }
+    fn search_name(&mut self, _name: &Name) -> SearchState<T> {
+        NotFinished
+    }
+    fn search_expression(&mut self, _expr: &Expression) -> SearchState<T> {
+        NotFinished
+    }
     fn search_with_pos(&mut self, _pos: &SrcPos) -> SearchState<T> {
         NotFinished
     }
@@ -111,6 +146,12 @@ impl<T, V: Search<T>> Search<T> for Option<V> {
     }
 }
 
+impl<T, V: Search<T>> Search<T> for Box<V> {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        (**self).search(searcher)
+    }
+}
+
 impl<T> Search<T> for LabeledSequentialStatement {
     fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
         searcher
@@ -157,15 +198,26 @@ impl<T> Search<T> for LabeledConcurrentStatement {
                     }
                     NotFound
                 }
-                // @TODO not searched
-                _ => NotFound,
+                ConcurrentStatement::ProcedureCall(ref call) => call.search(searcher),
+                ConcurrentStatement::Assert(ref assert) => {
+                    return_if!(assert.condition.search(searcher));
+                    return_if!(assert.report.search(searcher));
+                    assert.severity.search(searcher)
+                }
+                ConcurrentStatement::Assignment(ref target, ref rhs) => {
+                    return_if!(target.search(searcher));
+                    rhs.search(searcher)
+                }
+                ConcurrentStatement::Instance(ref instance) => instance.search(searcher),
             })
     }
 }
 
 impl<T> Search<T> for WithRef<Designator> {
     fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
-        searcher.search_designator_ref(self).or_else(|| NotFound)
+        searcher
+            .search_reference_kind(self, ReferenceKind::Read)
+            .or_else(|| searcher.search_designator_ref(self).or_else(|| NotFound))
     }
 }
 
@@ -192,6 +244,299 @@ impl<T> Search<T> for SelectedName {
     }
 }
 
+impl<T> Search<T> for Name {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        searcher.search_name(self).or_else(|| match self {
+            Name::Designator(designator) => designator.search(searcher),
+            Name::Selected(prefix, designator) => {
+                return_if!(prefix.search(searcher));
+                designator.search(searcher)
+            }
+            Name::SelectedAll(prefix) => prefix.search(searcher),
+            Name::Indexed(prefix, indexes) => {
+                return_if!(prefix.search(searcher));
+                indexes.search(searcher)
+            }
+            Name::Attribute(attr) => {
+                return_if!(attr.name.search(searcher));
+                attr.expr.search(searcher)
+            }
+            Name::CallOrIndexed(call) => call.search(searcher),
+            // @TODO slice discrete ranges and external names not yet searched
+            Name::Slice(prefix, _range) => prefix.search(searcher),
+            Name::External(_) => NotFound,
+        })
+    }
+}
+
+/// The target of a signal (`<=`) or variable (`:=`) assignment. Unlike a
+/// plain [`Name`] occurring elsewhere, the designator a target ultimately
+/// refers to is a [`ReferenceKind::Write`], not a `Read`.
+impl<T> Search<T> for Target {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            Target::Name(name) => name.search_as(searcher, ReferenceKind::Write),
+            Target::Aggregate(elements) => {
+                for element in elements.iter() {
+                    return_if!(element.search_as(searcher, ReferenceKind::Write));
+                }
+                NotFound
+            }
+        }
+    }
+}
+
+impl Name {
+    /// Like [`Search::search`] but reports the designator this name
+    /// ultimately resolves to as `kind` instead of the default `Read`. For a
+    /// selected name the prefix (the record object selected into) is still
+    /// a plain read and only the selected designator gets `kind`; an
+    /// indexed or sliced name passes `kind` on to its prefix (the array
+    /// object), and its index expressions are plain reads.
+    fn search_as<T>(
+        &self,
+        searcher: &mut impl Searcher<T>,
+        kind: ReferenceKind,
+    ) -> SearchResult<T> {
+        match self {
+            Name::Designator(designator) => searcher
+                .search_reference_kind(&designator.item, kind)
+                .or_else(|| {
+                    searcher
+                        .search_designator_ref(&designator.item)
+                        .or_else(|| NotFound)
+                }),
+            Name::Selected(prefix, designator) => {
+                return_if!(prefix.search(searcher));
+                searcher
+                    .search_reference_kind(&designator.item, kind)
+                    .or_else(|| {
+                        searcher
+                            .search_designator_ref(&designator.item)
+                            .or_else(|| NotFound)
+                    })
+            }
+            Name::Indexed(prefix, indexes) => {
+                return_if!(prefix.search_as(searcher, kind));
+                indexes.search(searcher)
+            }
+            Name::Slice(prefix, _range) => prefix.search_as(searcher, kind),
+            // `rec.all`, attributes, calls and external names are not
+            // themselves write targets; fall back to the default traversal.
+            _ => self.search(searcher),
+        }
+    }
+}
+
+impl WithPos<Name> {
+    fn search_as<T>(
+        &self,
+        searcher: &mut impl Searcher<T>,
+        kind: ReferenceKind,
+    ) -> SearchResult<T> {
+        searcher
+            .search_with_pos(&self.pos)
+            .or_else(|| self.item.search_as(searcher, kind))
+    }
+}
+
+impl<T> Search<T> for CallOrIndexed {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        return_if!(self.name.search(searcher));
+        self.parameters.search(searcher)
+    }
+}
+
+/// **Known incomplete:** `actual` is always searched as a plain read, even
+/// when `formal` resolves to an `out` or `inout` port or parameter, which
+/// should classify it as [`ReferenceKind::Write`] instead. Doing that
+/// requires resolving `formal` against the callee's interface list and
+/// reading its mode, and nothing in this purely syntactic `Search`
+/// traversal carries resolved semantic information (a `WithRef` only ever
+/// stores the declaration's position, not its mode) — that lookup belongs
+/// in the analysis pass that already resolves `formal`/`actual`, not here.
+/// Until that's wired through, `documentHighlight` will miss every
+/// port-map actual of an `out`/`inout` signal as a "write" occurrence.
+impl<T> Search<T> for AssociationElement {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        return_if!(self.formal.search(searcher));
+        self.actual.search(searcher)
+    }
+}
+
+impl<T> Search<T> for ActualPart {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            ActualPart::Expression(expr) => expr.search(searcher),
+            ActualPart::Open => NotFound,
+        }
+    }
+}
+
+impl<T> Search<T> for Expression {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        searcher.search_expression(self).or_else(|| match self {
+            Expression::Binary(_, left, right) => {
+                return_if!(left.search(searcher));
+                right.search(searcher)
+            }
+            Expression::Unary(_, operand) => operand.search(searcher),
+            Expression::Name(name) => name.search(searcher),
+            Expression::Aggregate(elements) => elements.search(searcher),
+            Expression::Qualified(qualified) => qualified.search(searcher),
+            Expression::New(allocator) => allocator.search(searcher),
+            Expression::Literal(_) => NotFound,
+        })
+    }
+}
+
+impl Expression {
+    /// Like [`Search::search`], but used when this expression occurs as an
+    /// aggregate-target element: a bare name is a write target, anything
+    /// else (a literal, a sub-aggregate, ...) falls back to a plain read.
+    fn search_as<T>(
+        &self,
+        searcher: &mut impl Searcher<T>,
+        kind: ReferenceKind,
+    ) -> SearchResult<T> {
+        match self {
+            Expression::Name(name) => name.search_as(searcher, kind),
+            _ => self.search(searcher),
+        }
+    }
+}
+
+impl WithPos<Expression> {
+    fn search_as<T>(
+        &self,
+        searcher: &mut impl Searcher<T>,
+        kind: ReferenceKind,
+    ) -> SearchResult<T> {
+        searcher
+            .search_with_pos(&self.pos)
+            .or_else(|| self.item.search_as(searcher, kind))
+    }
+}
+
+impl<T> Search<T> for QualifiedExpression {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        return_if!(self.type_mark.search(searcher));
+        self.expr.search(searcher)
+    }
+}
+
+impl<T> Search<T> for Allocator {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            Allocator::Qualified(qualified) => qualified.search(searcher),
+            Allocator::Subtype(subtype) => subtype.search(searcher),
+        }
+    }
+}
+
+impl<T> Search<T> for ElementAssociation {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            ElementAssociation::Positional(expr) => expr.search(searcher),
+            ElementAssociation::Named(choices, expr) => {
+                return_if!(choices.search(searcher));
+                expr.search(searcher)
+            }
+        }
+    }
+}
+
+impl ElementAssociation {
+    /// Like [`Search::search`], but used when this element occurs within an
+    /// aggregate `Target`: the choices (if any) are still a plain read, but
+    /// the element's expression is itself a name being assigned to.
+    fn search_as<T>(
+        &self,
+        searcher: &mut impl Searcher<T>,
+        kind: ReferenceKind,
+    ) -> SearchResult<T> {
+        match self {
+            ElementAssociation::Positional(expr) => expr.search_as(searcher, kind),
+            ElementAssociation::Named(choices, expr) => {
+                return_if!(choices.search(searcher));
+                expr.search_as(searcher, kind)
+            }
+        }
+    }
+}
+
+impl<T> Search<T> for Choice {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            Choice::Expression(expr) => expr.search(searcher),
+            // @TODO discrete range choices not yet searched
+            Choice::DiscreteRange(_) => NotFound,
+            Choice::Others => NotFound,
+        }
+    }
+}
+
+impl<T, W: Search<T>> Search<T> for AssignmentRightHand<W> {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            AssignmentRightHand::Simple(item) => item.search(searcher),
+            AssignmentRightHand::Conditional {
+                conditionals,
+                else_item,
+            } => {
+                for conditional in conditionals.iter() {
+                    return_if!(conditional.condition.search(searcher));
+                    return_if!(conditional.item.search(searcher));
+                }
+                else_item.search(searcher)
+            }
+            AssignmentRightHand::Selected {
+                expression,
+                selection,
+            } => {
+                return_if!(expression.search(searcher));
+                for alternative in selection.iter() {
+                    return_if!(alternative.item.search(searcher));
+                }
+                NotFound
+            }
+        }
+    }
+}
+
+impl<T> Search<T> for Waveform {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            Waveform::Elements(elements) => {
+                for element in elements.iter() {
+                    return_if!(element.value.search(searcher));
+                    return_if!(element.after.search(searcher));
+                }
+                NotFound
+            }
+            Waveform::Unaffected => NotFound,
+        }
+    }
+}
+
+impl<T> Search<T> for InstantiatedUnit {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        match self {
+            InstantiatedUnit::Component(name) => name.search(searcher),
+            InstantiatedUnit::Entity(name, _arch) => name.search(searcher),
+            InstantiatedUnit::Configuration(name) => name.search(searcher),
+        }
+    }
+}
+
+impl<T> Search<T> for InstantiationStatement {
+    fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
+        return_if!(self.unit.search(searcher));
+        return_if!(self.generic_map.search(searcher));
+        self.port_map.search(searcher)
+    }
+}
+
 impl<T> Search<T> for SubtypeIndication {
     fn search(&self, searcher: &mut impl Searcher<T>) -> SearchResult<T> {
         searcher.search_subtype_indication(&self).or_else(|| {
@@ -386,4 +731,238 @@ impl Searcher<SrcPos> for ReferenceSearcher {
             Finished(NotFound)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Resolves both the declaration and the [`ReferenceKind`] of the
+/// occurrence under the cursor, for `textDocument/documentHighlight`.
+pub struct ReferenceKindSearcher {
+    source: Source,
+    cursor: usize,
+}
+
+impl ReferenceKindSearcher {
+    pub fn new(source: &Source, cursor: usize) -> ReferenceKindSearcher {
+        ReferenceKindSearcher {
+            source: source.clone(),
+            cursor,
+        }
+    }
+}
+
+impl Searcher<(SrcPos, ReferenceKind)> for ReferenceKindSearcher {
+    fn search_with_pos(&mut self, pos: &SrcPos) -> SearchState<(SrcPos, ReferenceKind)> {
+        if pos.start <= self.cursor && self.cursor <= pos.end() {
+            NotFinished
+        } else {
+            Finished(NotFound)
+        }
+    }
+
+    fn search_reference_kind(
+        &mut self,
+        designator: &WithRef<Designator>,
+        kind: ReferenceKind,
+    ) -> SearchState<(SrcPos, ReferenceKind)> {
+        if let Some(ref reference) = designator.reference {
+            Finished(Found((reference.clone(), kind)))
+        } else {
+            Finished(NotFound)
+        }
+    }
+
+    fn search_source(&mut self, source: &Source) -> SearchState<(SrcPos, ReferenceKind)> {
+        if source == &self.source {
+            NotFinished
+        } else {
+            Finished(NotFound)
+        }
+    }
+}
+
+/// The kind of a [`DocumentSymbol`], mapping onto LSP `SymbolKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Entity,
+    Architecture,
+    Package,
+    PackageBody,
+    Configuration,
+    Port,
+    Generic,
+    Signal,
+    Variable,
+    Constant,
+    Type,
+    Subprogram,
+    Instance,
+}
+
+/// One entry in the hierarchical outline of a source file, for LSP
+/// `textDocument/documentSymbol`.
+#[derive(Debug, Clone)]
+pub struct DocumentSymbol {
+    pub kind: SymbolKind,
+    pub name: SrcPos,
+    pub span: SrcPos,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Which interface clause `OutlineSearcher` is currently walking, since
+/// `InterfaceDeclaration::Object` is shared between entity/package generics,
+/// entity ports and subprogram formal parameters with nothing in the AST
+/// node itself to tell them apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineClause {
+    Generic,
+    Port,
+    Parameter,
+}
+
+/// Walks a single source and accumulates every declaration it contains into
+/// a flat list of [`DocumentSymbol`]s, to be nested under the top-level
+/// design unit by the caller. Unlike `ReferenceSearcher`, no hook ever
+/// returns `Found`: `document_symbols` wants every declaration, not just
+/// the first one, so the traversal must never short-circuit on `Found` the
+/// way `return_if!` does elsewhere. Hooks return `Finished(NotFound)` only
+/// to skip other sources and subprogram subtrees already walked by a
+/// nested searcher.
+pub struct OutlineSearcher {
+    source: Source,
+    children: Vec<DocumentSymbol>,
+    clause: Option<OutlineClause>,
+}
+
+impl OutlineSearcher {
+    pub fn new(source: &Source) -> OutlineSearcher {
+        OutlineSearcher {
+            source: source.clone(),
+            children: Vec::new(),
+            clause: None,
+        }
+    }
+
+    /// Sets which interface clause subsequent `InterfaceDeclaration`s belong
+    /// to, or `None` once the generic/port clauses have been left. The
+    /// caller (`outline_of_unit`) is responsible for toggling this as it
+    /// drives the generic and port clauses through the searcher separately.
+    pub fn set_clause(&mut self, clause: Option<OutlineClause>) {
+        self.clause = clause;
+    }
+
+    fn push(&mut self, kind: SymbolKind, name: SrcPos) {
+        self.push_with_children(kind, name, Vec::new());
+    }
+
+    fn push_with_children(
+        &mut self,
+        kind: SymbolKind,
+        name: SrcPos,
+        children: Vec<DocumentSymbol>,
+    ) {
+        let span = name.clone();
+        self.children.push(DocumentSymbol {
+            kind,
+            name,
+            span,
+            children,
+        });
+    }
+
+    /// Consumes the searcher, wrapping the accumulated children under a
+    /// top-level symbol for the design unit that was searched.
+    pub fn finish_unit(self, kind: SymbolKind, name: SrcPos, span: SrcPos) -> DocumentSymbol {
+        DocumentSymbol {
+            kind,
+            name,
+            span,
+            children: self.children,
+        }
+    }
+}
+
+impl Searcher<()> for OutlineSearcher {
+    fn search_source(&mut self, source: &Source) -> SearchState<()> {
+        if source == &self.source {
+            NotFinished
+        } else {
+            Finished(NotFound)
+        }
+    }
+
+    fn search_declaration(&mut self, decl: &Declaration) -> SearchState<()> {
+        match decl {
+            Declaration::Object(object) => {
+                let kind = match object.class {
+                    ObjectClass::Constant => SymbolKind::Constant,
+                    ObjectClass::Variable => SymbolKind::Variable,
+                    ObjectClass::Signal => SymbolKind::Signal,
+                };
+                self.push(kind, object.ident.pos.clone());
+            }
+            Declaration::Type(typ) => {
+                self.push(SymbolKind::Type, typ.ident.pos.clone());
+            }
+            Declaration::SubprogramBody(body) => {
+                let mut nested = OutlineSearcher::new(&self.source);
+                nested.set_clause(Some(OutlineClause::Parameter));
+                body.specification.search(&mut nested);
+                nested.set_clause(None);
+                body.declarations.search(&mut nested);
+                self.push_with_children(
+                    SymbolKind::Subprogram,
+                    subprogram_name(&body.specification),
+                    nested.children,
+                );
+                // The nested searcher above already walked the
+                // specification and declarations with clause bookkeeping
+                // of its own; returning here instead of `NotFinished` stops
+                // the outer traversal from walking the same subtree again
+                // with the wrong (outer) clause in effect.
+                return Finished(NotFound);
+            }
+            Declaration::SubprogramDeclaration(decl) => {
+                let mut nested = OutlineSearcher::new(&self.source);
+                nested.set_clause(Some(OutlineClause::Parameter));
+                decl.search(&mut nested);
+                self.push_with_children(
+                    SymbolKind::Subprogram,
+                    subprogram_name(decl),
+                    nested.children,
+                );
+                return Finished(NotFound);
+            }
+            _ => {}
+        }
+        NotFinished
+    }
+
+    fn search_interface_declaration(&mut self, decl: &InterfaceDeclaration) -> SearchState<()> {
+        if let InterfaceDeclaration::Object(object) = decl {
+            let kind = match self.clause {
+                Some(OutlineClause::Generic) => SymbolKind::Generic,
+                Some(OutlineClause::Parameter) => SymbolKind::Variable,
+                Some(OutlineClause::Port) | None => SymbolKind::Port,
+            };
+            self.push(kind, object.ident.pos.clone());
+        }
+        NotFinished
+    }
+
+    fn search_labeled_concurrent_statement(
+        &mut self,
+        stmt: &LabeledConcurrentStatement,
+    ) -> SearchState<()> {
+        if let (Some(ref label), ConcurrentStatement::Instance(..)) = (&stmt.label, &stmt.statement)
+        {
+            self.push(SymbolKind::Instance, label.pos.clone());
+        }
+        NotFinished
+    }
+}
+
+fn subprogram_name(spec: &SubprogramDeclaration) -> SrcPos {
+    match spec {
+        SubprogramDeclaration::Function(decl) => decl.designator.pos.clone(),
+        SubprogramDeclaration::Procedure(decl) => decl.designator.pos.clone(),
+    }
+}